@@ -1,15 +1,89 @@
-use crate::debug::REGS_NAMES;
+use std::collections::HashMap;
+use std::io::Read;
+
+use crate::debug::{FREGS_NAMES, REGS_NAMES};
+use crate::jit;
 use crate::memory;
 use crate::opcode::*;
 use crate::registers;
 
-#[derive(Debug, Clone)]
+// A resolved instruction: the opcode/funct3/funct7 match has already run, so
+// re-fetching the same pc just calls `handler` directly. Operands are not
+// cached here since every exec_* handler re-extracts them from `instr`
+// anyway; caching them would mean threading them through every handler.
+#[derive(Clone, Copy)]
+struct DecodedInst {
+    handler: fn(&mut CPU, u32),
+}
+
+// a0-a6 hold syscall arguments, a7 holds the syscall number.
+const REG_A0: usize = 10;
+const REG_A7: usize = 17;
+
+// RISC-V Linux syscall numbers, a la <asm-generic/unistd.h>.
+const SYS_READ: u32 = 63;
+const SYS_WRITE: u32 = 64;
+const SYS_EXIT: u32 = 93;
+
+// funct12 of the MRET instruction (opcode CSR, funct3 PRIV).
+const MRET: i32 = 0x302;
+
+// machine-mode CSR addresses we implement.
+const CSR_MTVEC: usize = 0x305;
+const CSR_MEPC: usize = 0x341;
+const CSR_MCAUSE: usize = 0x342;
+
+// trap causes we can raise (mcause values for synchronous exceptions).
+const CAUSE_BREAKPOINT: u32 = 3;
+const CAUSE_ILLEGAL_INSTRUCTION: u32 = 2;
+const CAUSE_ECALL_FROM_M: u32 = 11;
+
+// fcsr CSR address (rounding mode + exception flags; unused for now, we
+// round with native f32 arithmetic).
+#[allow(dead_code)]
+const CSR_FCSR: usize = 0x003;
+
 pub struct CPU {
     // integer registers
     pub xregs: registers::XREGS,
     pub pc: u32,
 
     pub bus: memory::BUS,
+
+    // machine-mode control and status registers, indexed by CSR address
+    pub csrs: [u32; 4096],
+
+    // RV32F single-precision floating-point registers
+    pub fregs: [f32; 32],
+
+    // set by `exec_ecall` on SYS_EXIT so the fetch-decode-execute loop can stop
+    pub halted: bool,
+    pub exit_code: i32,
+
+    // decode-once cache, keyed by the pc the instruction was fetched from
+    decode_cache: HashMap<u32, DecodedInst>,
+
+    // when set, `run`/`step` compile and execute basic blocks natively
+    // instead of interpreting each instruction
+    pub jit_enabled: bool,
+    jit_cache: HashMap<u32, jit::CompiledBlock>,
+
+    // entry pc of the block currently running native code, if any; a store
+    // from inside that block must not free its own `CompiledBlock` (the
+    // code would be munmap'd out from under the return address), so
+    // eviction of this key is deferred until the block returns
+    jit_executing: Option<u32>,
+    jit_pending_invalidate: Vec<u32>,
+
+    // address of `self` the first time `step` ran with `jit_enabled`; every
+    // `CompiledBlock` bakes `cpu_ptr`/`regs_ptr` in as absolute immediates,
+    // so moving a `CPU` (reassigning it, returning it by value, pushing it
+    // into a `Vec`, ...) after JIT-compiling any block leaves every cached
+    // entry dangling. There is no safe way to relocate the baked addresses,
+    // so once JIT is enabled a `CPU` must stay put for its remaining
+    // lifetime; `step` checks this on every call and panics loudly instead
+    // of letting it corrupt memory silently.
+    jit_base_addr: Option<u64>,
 }
 
 impl CPU {
@@ -18,6 +92,16 @@ impl CPU {
             xregs: registers::XREGS::new(),
             pc: memory::MEM_BASE,
             bus: memory::BUS::new(),
+            csrs: [0; 4096],
+            fregs: [0.0; 32],
+            halted: false,
+            exit_code: 0,
+            jit_enabled: false,
+            jit_cache: HashMap::new(),
+            jit_executing: None,
+            jit_pending_invalidate: Vec::new(),
+            jit_base_addr: None,
+            decode_cache: HashMap::new(),
         };
         cpu.xregs.regs[2] = memory::MEM_BASE + memory::MEM_SIZE; // Set stack pointer
         cpu.pc = memory::MEM_BASE;
@@ -30,98 +114,212 @@ impl CPU {
     }
 
     pub fn execute(&mut self, instr: u32) {
-        let opcode = instr & 0x7f;
-        let funct3 = (instr >> 12) & 0x7;
-        let funct7 = (instr >> 25) & 0x7f;
         self.xregs.regs[0] = 0; // x0 hardwired to 0 at each cycle
 
-        match opcode {
-            LUI => exec_lui(self, instr),
-            AUIPC => exec_auipc(self, instr),
-            JAL => exec_jal(self, instr),
-            JALR => exec_jalr(self, instr),
-            B_TYPE => match funct3 {
-                BEQ => exec_beq(self, instr),
-                BNE => exec_bne(self, instr),
-                BLT => exec_blt(self, instr),
-                BGE => exec_bge(self, instr),
-                BLTU => exec_bltu(self, instr),
-                BGEU => exec_bgeu(self, instr),
-                _ => panic!(),
+        let decoded = *self
+            .decode_cache
+            .entry(self.pc)
+            .or_insert_with(|| decode(instr));
+        (decoded.handler)(self, instr);
+    }
+
+    // Runs until `halted` is set (typically by an `exit` syscall), either
+    // fully interpreted or, with `jit_enabled`, by executing compiled
+    // native blocks and interpreting only their branch/jump/ecall tail.
+    pub fn run(&mut self) -> i32 {
+        while !self.halted {
+            self.step();
+        }
+        self.exit_code
+    }
+
+    fn step(&mut self) {
+        if self.jit_enabled {
+            let self_addr = self as *const CPU as u64;
+            match self.jit_base_addr {
+                Some(addr) => assert_eq!(
+                    addr, self_addr,
+                    "CPU moved while jit_enabled: every CompiledBlock bakes in absolute \
+                     pointers to the CPU it was compiled against, so this would run stale \
+                     blocks against freed or unrelated memory"
+                ),
+                None => self.jit_base_addr = Some(self_addr),
+            }
+
+            let pc = self.pc;
+            let cached = self.jit_cache.get(&pc).map(|b| (b.entry, b.end));
+            let (entry, end) = match cached {
+                Some(info) => info,
+                None => {
+                    let block = jit::compile_block(self, pc);
+                    let info = (block.entry, block.end);
+                    self.jit_cache.insert(pc, block);
+                    info
+                }
+            };
+            if end > pc {
+                self.jit_executing = Some(pc);
+                unsafe { entry() };
+                self.jit_executing = None;
+                for start in self.jit_pending_invalidate.drain(..) {
+                    self.jit_cache.remove(&start);
+                }
+                self.pc = end;
+            }
+            if self.halted {
+                return;
+            }
+        }
+
+        let instr = self.fetch();
+        self.execute(instr);
+        self.pc = self.pc.wrapping_add(4);
+    }
+
+    // Drops the compiled block starting at `start`, unless it is the one
+    // currently executing (self-modifying code inside a fallback-called
+    // exec_* handler), in which case freeing it is deferred until `step`
+    // regains control and the block's code is no longer on the stack.
+    fn evict_jit_block(&mut self, start: u32) {
+        if self.jit_executing == Some(start) {
+            self.jit_pending_invalidate.push(start);
+        } else {
+            self.jit_cache.remove(&start);
+        }
+    }
+}
+
+// Runs the opcode/funct3/funct7 match once and resolves it to a handler
+// function pointer, so `execute` only has to pay for this on the first
+// visit to a given pc. Operands are not pre-extracted; the handler decodes
+// them from `instr` on every call.
+fn decode(instr: u32) -> DecodedInst {
+    let opcode = instr & 0x7f;
+    let funct3 = (instr >> 12) & 0x7;
+    let funct7 = (instr >> 25) & 0x7f;
+
+    let handler: fn(&mut CPU, u32) = match opcode {
+        LUI => exec_lui,
+        AUIPC => exec_auipc,
+        JAL => exec_jal,
+        JALR => exec_jalr,
+        B_TYPE => match funct3 {
+            BEQ => exec_beq,
+            BNE => exec_bne,
+            BLT => exec_blt,
+            BGE => exec_bge,
+            BLTU => exec_bltu,
+            BGEU => exec_bgeu,
+            _ => exec_illegal,
+        },
+        LOAD => match funct3 {
+            LB => exec_lb,
+            LH => exec_lh,
+            LW => exec_lw,
+            LBU => exec_lbu,
+            LHU => exec_lhu,
+            LWU => exec_lwu,
+            _ => exec_illegal,
+        },
+        S_TYPE => match funct3 {
+            SB => exec_sb,
+            SH => exec_sh,
+            SW => exec_sw,
+            _ => exec_illegal,
+        },
+        I_TYPE => match funct3 {
+            ADDI => exec_addi,
+            SLLI => exec_slli,
+            SLTI => exec_slti,
+            SLTIU => exec_sltiu,
+            XORI => exec_xori,
+            SRI => match funct7 {
+                SRLI => exec_srli,
+                SRAI => exec_srai,
+                _ => exec_illegal,
             },
-            LOAD => match funct3 {
-                LB => exec_lb(self, instr),
-                LH => exec_lh(self, instr),
-                LW => exec_lw(self, instr),
-                LBU => exec_lbu(self, instr),
-                LHU => exec_lhu(self, instr),
-                LWU => exec_lwu(self, instr),
-                _ => panic!(),
+            ORI => exec_ori,
+            ANDI => exec_andi,
+            _ => exec_illegal,
+        },
+        R_TYPE => match funct3 {
+            ADDSUB => match funct7 {
+                ADD => exec_add,
+                SUB => exec_sub,
+                _ => exec_nop,
             },
-            S_TYPE => match funct3 {
-                SB => exec_sb(self, instr),
-                SH => exec_sh(self, instr),
-                SW => exec_sw(self, instr),
-                _ => panic!(),
+            SLL => exec_sll,
+            SLT => exec_slt,
+            SLTU => exec_sltu,
+            XOR => exec_xor,
+            SR => match funct7 {
+                SRL => exec_srl,
+                SRA => exec_sra,
+                _ => exec_nop,
             },
-            I_TYPE => match funct3 {
-                ADDI => exec_addi(self, instr),
-                SLLI => exec_slli(self, instr),
-                SLTI => exec_slti(self, instr),
-                SLTIU => exec_sltiu(self, instr),
-                XORI => exec_xori(self, instr),
-                SRI => match funct7 {
-                    SRLI => exec_srli(self, instr),
-                    SRAI => exec_srai(self, instr),
-                    _ => panic!(),
-                },
-                ORI => exec_ori(self, instr),
-                ANDI => exec_andi(self, instr),
-                _ => {
-                    panic!("malformed I type instruction");
-                }
+            OR => exec_or,
+            AND => exec_and,
+            _ => exec_illegal,
+        },
+        FENCE => match funct3 {
+            FENCE_I => exec_fence_i,
+            _ => exec_fence,
+        },
+        CSR => match funct3 {
+            ECALL => match imm_i(instr) {
+                0x0 => exec_ecall,
+                0x1 => exec_ebreak,
+                MRET => exec_mret,
+                _ => exec_illegal,
             },
-            R_TYPE => match funct3 {
-                ADDSUB => match funct7 {
-                    ADD => exec_add(self, instr),
-                    SUB => exec_sub(self, instr),
-                    _ => (),
-                },
-                SLL => exec_sll(self, instr),
-                SLT => exec_slt(self, instr),
-                SLTU => exec_sltu(self, instr),
-                XOR => exec_xor(self, instr),
-                SR => match funct7 {
-                    SRL => exec_srl(self, instr),
-                    SRA => exec_sra(self, instr),
-                    _ => (),
-                },
-                OR => exec_or(self, instr),
-                AND => exec_and(self, instr),
-                _ => {
-                    panic!("malformed I type instruction");
-                }
+            CSRRW => exec_csrrw,
+            CSRRS => exec_csrrs,
+            CSRRC => exec_csrrc,
+            CSRRWI => exec_csrrwi,
+            CSRRSI => exec_csrrsi,
+            CSRRCI => exec_csrrci,
+            _ => exec_illegal,
+        },
+        LOAD_FP => match funct3 {
+            FLW => exec_flw,
+            _ => exec_illegal,
+        },
+        STORE_FP => match funct3 {
+            FSW => exec_fsw,
+            _ => exec_illegal,
+        },
+        OP_FP => match funct7 {
+            FADD_S => exec_fadd_s,
+            FSUB_S => exec_fsub_s,
+            FMUL_S => exec_fmul_s,
+            FDIV_S => exec_fdiv_s,
+            FSQRT_S => exec_fsqrt_s,
+            FSGNJ_S => match funct3 {
+                FSGNJ => exec_fsgnj_s,
+                FSGNJN => exec_fsgnjn_s,
+                FSGNJX => exec_fsgnjx_s,
+                _ => exec_illegal,
             },
-            FENCE => exec_fence(self, instr),
-            CSR => match (funct3) {
-                ECALL | EBREAK => match imm_i(instr) {
-                    0x0 => exec_ecall(self, instr),
-                    0x1 => exec_ebreak(self, instr),
-                    _ => (),
-                },
-                CSRRW => exec_csrrw(self, instr),
-                CSRRS => exec_csrrs(self, instr),
-                CSRRC => exec_csrrc(self, instr),
-                CSRRWI => exec_csrrwi(self, instr),
-                CSRRSI => exec_csrrsi(self, instr),
-                CSRRCI => exec_csrrci(self, instr),
-                _ => {
-                    panic!("malformed CSR instruction");
-                }
+            FCMP_S => match funct3 {
+                FEQ => exec_feq_s,
+                FLT => exec_flt_s,
+                FLE => exec_fle_s,
+                _ => exec_illegal,
             },
-            _ => panic!("invalid instr {}, opcode: {:b}", instr, opcode),
-        }
-    }
+            FCVT_W_S => exec_fcvt_w_s,
+            FCVT_S_W => exec_fcvt_s_w,
+            _ => exec_illegal,
+        },
+        _ => exec_illegal,
+    };
+
+    DecodedInst { handler }
+}
+
+fn exec_nop(_cpu: &mut CPU, _instr: u32) {}
+
+pub(crate) fn exec_illegal(cpu: &mut CPU, _instr: u32) {
+    take_trap(cpu, CAUSE_ILLEGAL_INSTRUCTION);
 }
 
 // RV32I
@@ -232,18 +430,44 @@ pub fn exec_sb(cpu: &mut CPU, instr: u32) {
     let addr = (cpu.xregs.regs[rs1(instr) as usize] as i32).wrapping_add(imm) as u32;
     let val = cpu.xregs.regs[rs2(instr) as usize] & std::u8::MAX as u32;
     cpu.bus.store(addr, 8, val);
+    invalidate_caches(cpu, addr, 1);
 }
 pub fn exec_sh(cpu: &mut CPU, instr: u32) {
     let imm = imm_s(instr) as i32;
     let addr = (cpu.xregs.regs[rs1(instr) as usize] as i32).wrapping_add(imm) as u32;
     let val = cpu.xregs.regs[rs2(instr) as usize] & std::u16::MAX as u32;
     cpu.bus.store(addr, 16, val);
+    invalidate_caches(cpu, addr, 2);
 }
 pub fn exec_sw(cpu: &mut CPU, instr: u32) {
     let imm = imm_s(instr) as i32;
     let addr = (cpu.xregs.regs[rs1(instr) as usize] as i32).wrapping_add(imm) as u32;
     let val = cpu.xregs.regs[rs2(instr) as usize] & std::u32::MAX as u32;
     cpu.bus.store(addr, 32, val);
+    invalidate_caches(cpu, addr, 4);
+}
+
+// Drops any decoded/compiled instruction covering the stored bytes
+// [addr, addr + len), since self-modifying code just invalidated it. A
+// sub-word store (sb/sh) can still straddle the pc an instruction word was
+// decoded at, so every word the range touches is evicted, not just `addr`.
+fn invalidate_caches(cpu: &mut CPU, addr: u32, len: u32) {
+    let end = addr.wrapping_add(len);
+    let mut word = addr & !0x3;
+    while word < end {
+        cpu.decode_cache.remove(&word);
+        word = word.wrapping_add(4);
+    }
+
+    let starts: Vec<u32> = cpu
+        .jit_cache
+        .iter()
+        .filter(|(&start, block)| start < end && addr < block.end)
+        .map(|(&start, _)| start)
+        .collect();
+    for start in starts {
+        cpu.evict_jit_block(start);
+    }
 }
 pub fn exec_addi(cpu: &mut CPU, instr: u32) {
     let imm = imm_i(instr);
@@ -335,15 +559,195 @@ pub fn exec_and(cpu: &mut CPU, instr: u32) {
         cpu.xregs.regs[rs1(instr) as usize] & cpu.xregs.regs[rs2(instr) as usize];
 }
 pub fn exec_fence(cpu: &mut CPU, instr: u32) {}
-pub fn exec_fence_i(cpu: &mut CPU, instr: u32) {}
-pub fn exec_ecall(cpu: &mut CPU, instr: u32) {}
-pub fn exec_ebreak(cpu: &mut CPU, instr: u32) {}
-pub fn exec_csrrw(cpu: &mut CPU, instr: u32) {}
-pub fn exec_csrrs(cpu: &mut CPU, instr: u32) {}
-pub fn exec_csrrc(cpu: &mut CPU, instr: u32) {}
-pub fn exec_csrrwi(cpu: &mut CPU, instr: u32) {}
-pub fn exec_csrrsi(cpu: &mut CPU, instr: u32) {}
-pub fn exec_csrrci(cpu: &mut CPU, instr: u32) {}
+pub fn exec_fence_i(cpu: &mut CPU, _instr: u32) {
+    cpu.decode_cache.clear();
+    let starts: Vec<u32> = cpu.jit_cache.keys().copied().collect();
+    for start in starts {
+        cpu.evict_jit_block(start);
+    }
+}
+pub fn exec_ecall(cpu: &mut CPU, _instr: u32) {
+    let which = cpu.xregs.regs[REG_A7];
+    let a0 = cpu.xregs.regs[REG_A0];
+    let a1 = cpu.xregs.regs[REG_A0 + 1];
+    let a2 = cpu.xregs.regs[REG_A0 + 2];
+
+    // Host syscalls are emulated directly and return to the instruction
+    // after the ecall; anything else traps to the guest's mtvec handler.
+    match which {
+        SYS_EXIT => {
+            cpu.halted = true;
+            cpu.exit_code = a0 as i32;
+        }
+        SYS_WRITE => {
+            let (fd, ptr, len) = (a0, a1, a2);
+            let mut buf = Vec::with_capacity(len as usize);
+            for i in 0..len {
+                buf.push(cpu.bus.load(ptr.wrapping_add(i), 8) as u8);
+            }
+            if fd == 2 {
+                eprint!("{}", String::from_utf8_lossy(&buf));
+            } else {
+                print!("{}", String::from_utf8_lossy(&buf));
+            }
+            cpu.xregs.regs[REG_A0] = len;
+        }
+        SYS_READ => {
+            let (fd, ptr, len) = (a0, a1, a2);
+            let mut buf = vec![0u8; len as usize];
+            let n = if fd == 0 {
+                std::io::stdin().read(&mut buf).unwrap_or(0)
+            } else {
+                0
+            };
+            for (i, byte) in buf[..n].iter().enumerate() {
+                cpu.bus.store(ptr.wrapping_add(i as u32), 8, *byte as u32);
+            }
+            cpu.xregs.regs[REG_A0] = n as u32;
+        }
+        _ => take_trap(cpu, CAUSE_ECALL_FROM_M),
+    }
+}
+pub fn exec_ebreak(cpu: &mut CPU, _instr: u32) {
+    take_trap(cpu, CAUSE_BREAKPOINT);
+}
+pub fn exec_mret(cpu: &mut CPU, _instr: u32) {
+    // return from a trap: resume at the instruction mepc points to
+    cpu.pc = cpu.csrs[CSR_MEPC].wrapping_sub(4);
+}
+pub fn exec_csrrw(cpu: &mut CPU, instr: u32) {
+    let csr = csr_addr(instr);
+    let old = cpu.csrs[csr];
+    cpu.csrs[csr] = cpu.xregs.regs[rs1(instr) as usize];
+    cpu.xregs.regs[rd(instr) as usize] = old;
+}
+pub fn exec_csrrs(cpu: &mut CPU, instr: u32) {
+    let csr = csr_addr(instr);
+    let old = cpu.csrs[csr];
+    let rs1 = rs1(instr) as usize;
+    if rs1 != 0 {
+        cpu.csrs[csr] = old | cpu.xregs.regs[rs1];
+    }
+    cpu.xregs.regs[rd(instr) as usize] = old;
+}
+pub fn exec_csrrc(cpu: &mut CPU, instr: u32) {
+    let csr = csr_addr(instr);
+    let old = cpu.csrs[csr];
+    let rs1 = rs1(instr) as usize;
+    if rs1 != 0 {
+        cpu.csrs[csr] = old & !cpu.xregs.regs[rs1];
+    }
+    cpu.xregs.regs[rd(instr) as usize] = old;
+}
+pub fn exec_csrrwi(cpu: &mut CPU, instr: u32) {
+    let csr = csr_addr(instr);
+    let zimm = rs1(instr); // the rs1 field is a 5-bit immediate here
+    cpu.xregs.regs[rd(instr) as usize] = cpu.csrs[csr];
+    cpu.csrs[csr] = zimm;
+}
+pub fn exec_csrrsi(cpu: &mut CPU, instr: u32) {
+    let csr = csr_addr(instr);
+    let zimm = rs1(instr);
+    let old = cpu.csrs[csr];
+    if zimm != 0 {
+        cpu.csrs[csr] = old | zimm;
+    }
+    cpu.xregs.regs[rd(instr) as usize] = old;
+}
+pub fn exec_csrrci(cpu: &mut CPU, instr: u32) {
+    let csr = csr_addr(instr);
+    let zimm = rs1(instr);
+    let old = cpu.csrs[csr];
+    if zimm != 0 {
+        cpu.csrs[csr] = old & !zimm;
+    }
+    cpu.xregs.regs[rd(instr) as usize] = old;
+}
+
+fn csr_addr(instr: u32) -> usize {
+    ((instr >> 20) & 0xfff) as usize
+}
+
+// Saves trapping state into the machine-mode CSRs and redirects pc to the
+// trap handler at mtvec. see page 23 at
+// https://riscv.org/wp-content/uploads/2017/05/riscv-privileged-v1.10.pdf
+fn take_trap(cpu: &mut CPU, cause: u32) {
+    cpu.csrs[CSR_MEPC] = cpu.pc;
+    cpu.csrs[CSR_MCAUSE] = cause;
+    cpu.pc = cpu.csrs[CSR_MTVEC].wrapping_sub(4);
+}
+
+// RV32F
+// see page 78 at https://riscv.org/wp-content/uploads/2016/06/riscv-spec-v2.1.pdf
+pub fn exec_flw(cpu: &mut CPU, instr: u32) {
+    let imm = imm_i(instr);
+    dump_format_instr_fp(cpu, instr);
+    let bits = cpu.bus.load(
+        (cpu.xregs.regs[rs1(instr) as usize] as i32).wrapping_add(imm) as u32,
+        32,
+    );
+    cpu.fregs[rd(instr) as usize] = f32::from_bits(bits);
+}
+pub fn exec_fsw(cpu: &mut CPU, instr: u32) {
+    let imm = imm_s(instr);
+    dump_format_instr_fp(cpu, instr);
+    let addr = (cpu.xregs.regs[rs1(instr) as usize] as i32).wrapping_add(imm) as u32;
+    cpu.bus.store(addr, 32, cpu.fregs[rs2(instr) as usize].to_bits());
+    invalidate_caches(cpu, addr, 4);
+}
+pub fn exec_fadd_s(cpu: &mut CPU, instr: u32) {
+    cpu.fregs[rd(instr) as usize] =
+        cpu.fregs[rs1(instr) as usize] + cpu.fregs[rs2(instr) as usize];
+}
+pub fn exec_fsub_s(cpu: &mut CPU, instr: u32) {
+    cpu.fregs[rd(instr) as usize] =
+        cpu.fregs[rs1(instr) as usize] - cpu.fregs[rs2(instr) as usize];
+}
+pub fn exec_fmul_s(cpu: &mut CPU, instr: u32) {
+    cpu.fregs[rd(instr) as usize] =
+        cpu.fregs[rs1(instr) as usize] * cpu.fregs[rs2(instr) as usize];
+}
+pub fn exec_fdiv_s(cpu: &mut CPU, instr: u32) {
+    cpu.fregs[rd(instr) as usize] =
+        cpu.fregs[rs1(instr) as usize] / cpu.fregs[rs2(instr) as usize];
+}
+pub fn exec_fsqrt_s(cpu: &mut CPU, instr: u32) {
+    cpu.fregs[rd(instr) as usize] = cpu.fregs[rs1(instr) as usize].sqrt();
+}
+pub fn exec_fsgnj_s(cpu: &mut CPU, instr: u32) {
+    cpu.fregs[rd(instr) as usize] = cpu.fregs[rs1(instr) as usize]
+        .abs()
+        .copysign(cpu.fregs[rs2(instr) as usize]);
+}
+pub fn exec_fsgnjn_s(cpu: &mut CPU, instr: u32) {
+    cpu.fregs[rd(instr) as usize] = cpu.fregs[rs1(instr) as usize]
+        .abs()
+        .copysign(-cpu.fregs[rs2(instr) as usize]);
+}
+pub fn exec_fsgnjx_s(cpu: &mut CPU, instr: u32) {
+    let sign = cpu.fregs[rs1(instr) as usize].is_sign_negative()
+        ^ cpu.fregs[rs2(instr) as usize].is_sign_negative();
+    let mag = cpu.fregs[rs1(instr) as usize].abs();
+    cpu.fregs[rd(instr) as usize] = if sign { -mag } else { mag };
+}
+pub fn exec_feq_s(cpu: &mut CPU, instr: u32) {
+    cpu.xregs.regs[rd(instr) as usize] =
+        (cpu.fregs[rs1(instr) as usize] == cpu.fregs[rs2(instr) as usize]) as u32;
+}
+pub fn exec_flt_s(cpu: &mut CPU, instr: u32) {
+    cpu.xregs.regs[rd(instr) as usize] =
+        (cpu.fregs[rs1(instr) as usize] < cpu.fregs[rs2(instr) as usize]) as u32;
+}
+pub fn exec_fle_s(cpu: &mut CPU, instr: u32) {
+    cpu.xregs.regs[rd(instr) as usize] =
+        (cpu.fregs[rs1(instr) as usize] <= cpu.fregs[rs2(instr) as usize]) as u32;
+}
+pub fn exec_fcvt_w_s(cpu: &mut CPU, instr: u32) {
+    cpu.xregs.regs[rd(instr) as usize] = cpu.fregs[rs1(instr) as usize] as i32 as u32;
+}
+pub fn exec_fcvt_s_w(cpu: &mut CPU, instr: u32) {
+    cpu.fregs[rd(instr) as usize] = cpu.xregs.regs[rs1(instr) as usize] as i32 as f32;
+}
 
 fn dump_format_instr_r(cpu: &CPU, instr: u32) {
     println!(
@@ -409,3 +813,12 @@ fn dump_format_instr_u(cpu: &CPU, instr: u32) {
         imm_u(instr) as u32,
     );
 }
+fn dump_format_instr_fp(cpu: &CPU, instr: u32) {
+    println!(
+        "{}<- {}: {:#x}, imm: {:#x}",
+        FREGS_NAMES[rd(instr) as usize],
+        REGS_NAMES[rs1(instr) as usize],
+        cpu.xregs.regs[rs1(instr) as usize],
+        imm_i(instr) as i32,
+    );
+}
@@ -0,0 +1,119 @@
+// Minimal little-endian 32-bit RISC-V ELF loader.
+// see https://refspecs.linuxfoundation.org/elf/elf.pdf
+
+use crate::cpu::CPU;
+
+const EI_CLASS: usize = 4;
+const ELFCLASS32: u8 = 1;
+const EI_DATA: usize = 5;
+const ELFDATA2LSB: u8 = 1;
+const EM_RISCV: u16 = 243;
+const PT_LOAD: u32 = 1;
+
+#[derive(Debug)]
+pub enum ElfError {
+    TooShort,
+    NotElf,
+    Not32Bit,
+    NotLittleEndian,
+    NotRiscv,
+    BadProgramHeaders,
+    SegmentOutOfBounds,
+}
+
+struct ProgramHeader {
+    p_type: u32,
+    p_offset: u32,
+    p_vaddr: u32,
+    p_filesz: u32,
+    p_memsz: u32,
+}
+
+fn read_u16(bytes: &[u8], off: usize) -> u16 {
+    u16::from_le_bytes([bytes[off], bytes[off + 1]])
+}
+
+fn read_u32(bytes: &[u8], off: usize) -> u32 {
+    u32::from_le_bytes([
+        bytes[off],
+        bytes[off + 1],
+        bytes[off + 2],
+        bytes[off + 3],
+    ])
+}
+
+impl CPU {
+    // Parses `bytes` as an ELF32 RISC-V executable, loads its PT_LOAD
+    // segments into `self.bus`, and sets `self.pc` to the entry point.
+    pub fn load_elf(&mut self, bytes: &[u8]) -> Result<(), ElfError> {
+        if bytes.len() < 52 {
+            return Err(ElfError::TooShort);
+        }
+        if &bytes[0..4] != b"\x7fELF" {
+            return Err(ElfError::NotElf);
+        }
+        if bytes[EI_CLASS] != ELFCLASS32 {
+            return Err(ElfError::Not32Bit);
+        }
+        if bytes[EI_DATA] != ELFDATA2LSB {
+            return Err(ElfError::NotLittleEndian);
+        }
+
+        let e_machine = read_u16(bytes, 18);
+        if e_machine != EM_RISCV {
+            return Err(ElfError::NotRiscv);
+        }
+
+        let e_entry = read_u32(bytes, 24);
+        let e_phoff = read_u32(bytes, 28) as usize;
+        let e_phentsize = read_u16(bytes, 42) as usize;
+        let e_phnum = read_u16(bytes, 44) as usize;
+
+        // A conforming ELF32 program header is 32 bytes; anything smaller
+        // doesn't have room for the fields we read out of it below.
+        if e_phentsize < 32 {
+            return Err(ElfError::BadProgramHeaders);
+        }
+        let ph_table_end = e_phentsize
+            .checked_mul(e_phnum)
+            .and_then(|len| e_phoff.checked_add(len))
+            .ok_or(ElfError::BadProgramHeaders)?;
+        if ph_table_end > bytes.len() {
+            return Err(ElfError::BadProgramHeaders);
+        }
+
+        for i in 0..e_phnum {
+            let ph_off = e_phoff + i * e_phentsize;
+            let ph = ProgramHeader {
+                p_type: read_u32(bytes, ph_off),
+                p_offset: read_u32(bytes, ph_off + 4),
+                p_vaddr: read_u32(bytes, ph_off + 8),
+                p_filesz: read_u32(bytes, ph_off + 16),
+                p_memsz: read_u32(bytes, ph_off + 20),
+            };
+            if ph.p_type != PT_LOAD {
+                continue;
+            }
+
+            let file_end = (ph.p_offset as u64) + (ph.p_filesz as u64);
+            if ph.p_filesz > ph.p_memsz || file_end > bytes.len() as u64 {
+                return Err(ElfError::SegmentOutOfBounds);
+            }
+            if ph.p_vaddr.checked_add(ph.p_memsz).is_none() {
+                return Err(ElfError::SegmentOutOfBounds);
+            }
+
+            for j in 0..ph.p_memsz {
+                let byte = if j < ph.p_filesz {
+                    bytes[(ph.p_offset + j) as usize]
+                } else {
+                    0 // zero-fill .bss
+                };
+                self.bus.store(ph.p_vaddr + j, 8, byte as u32);
+            }
+        }
+
+        self.pc = e_entry;
+        Ok(())
+    }
+}
@@ -0,0 +1,388 @@
+// A minimal basic-block JIT: straight-line runs of arithmetic RV32I
+// instructions are translated to native x86-64, everything else falls back
+// to a `call` into the existing `exec_*` interpreter functions. Blocks are
+// cached per entry pc and invalidated when code they cover is overwritten.
+//
+// Unix only (uses mmap/mprotect directly instead of a dependency).
+
+use std::os::raw::{c_int, c_void};
+
+use crate::cpu::{
+    self, exec_auipc, exec_csrrc, exec_csrrci, exec_csrrs, exec_csrrsi, exec_csrrw, exec_csrrwi,
+    exec_fadd_s, exec_fcvt_s_w, exec_fcvt_w_s, exec_fdiv_s, exec_feq_s, exec_fence, exec_fence_i,
+    exec_fle_s, exec_flt_s, exec_flw, exec_fmul_s, exec_fsgnj_s, exec_fsgnjn_s, exec_fsgnjx_s,
+    exec_fsqrt_s, exec_fsub_s, exec_fsw, exec_lb, exec_lbu, exec_lh, exec_lhu, exec_lui, exec_lw,
+    exec_lwu, exec_sb, exec_sh, exec_sll, exec_slli, exec_slt, exec_sltu, exec_sra, exec_srai,
+    exec_srl, exec_srli, exec_sw, CPU,
+};
+use crate::opcode::*;
+
+const PROT_READ: c_int = 0x1;
+const PROT_WRITE: c_int = 0x2;
+const PROT_EXEC: c_int = 0x4;
+const MAP_PRIVATE: c_int = 0x02;
+const MAP_ANONYMOUS: c_int = 0x20;
+
+extern "C" {
+    fn mmap(
+        addr: *mut c_void,
+        len: usize,
+        prot: c_int,
+        flags: c_int,
+        fd: c_int,
+        offset: i64,
+    ) -> *mut c_void;
+    fn mprotect(addr: *mut c_void, len: usize, prot: c_int) -> c_int;
+    fn munmap(addr: *mut c_void, len: usize) -> c_int;
+}
+
+fn mmap_failed(ptr: *mut c_void) -> bool {
+    ptr as isize == -1
+}
+
+// A fixed-capacity buffer of raw machine code backed by an mmap'd region of
+// `cap` bytes, made executable once code generation for a block is done.
+// It does not grow: pushing past `cap` panics.
+pub struct CodeBuffer {
+    ptr: *mut u8,
+    len: usize,
+    cap: usize,
+}
+
+impl CodeBuffer {
+    pub fn new(cap: usize) -> Self {
+        let ptr = unsafe {
+            mmap(
+                std::ptr::null_mut(),
+                cap,
+                PROT_READ | PROT_WRITE,
+                MAP_PRIVATE | MAP_ANONYMOUS,
+                -1,
+                0,
+            )
+        };
+        assert!(!mmap_failed(ptr), "mmap failed for JIT code buffer");
+        CodeBuffer {
+            ptr: ptr as *mut u8,
+            len: 0,
+            cap,
+        }
+    }
+
+    pub fn addr(&self) -> usize {
+        self.ptr as usize + self.len
+    }
+
+    pub fn push_u8(&mut self, byte: u8) {
+        assert!(self.len < self.cap, "JIT code buffer overflow");
+        unsafe { self.ptr.add(self.len).write(byte) };
+        self.len += 1;
+    }
+
+    pub fn push_u32(&mut self, val: u32) {
+        for byte in val.to_le_bytes() {
+            self.push_u8(byte);
+        }
+    }
+
+    pub fn push_u64(&mut self, val: u64) {
+        for byte in val.to_le_bytes() {
+            self.push_u8(byte);
+        }
+    }
+
+    pub fn make_executable(&mut self) {
+        let res = unsafe { mprotect(self.ptr as *mut c_void, self.cap, PROT_READ | PROT_EXEC) };
+        assert_eq!(res, 0, "mprotect failed to make JIT code buffer executable");
+    }
+}
+
+impl Drop for CodeBuffer {
+    fn drop(&mut self) {
+        unsafe { munmap(self.ptr as *mut c_void, self.cap) };
+    }
+}
+
+pub struct CompiledBlock {
+    #[allow(dead_code)] // keeps the mmap region alive for as long as `entry` is callable
+    code: CodeBuffer,
+    pub entry: unsafe extern "C" fn(),
+    pub end: u32,
+}
+
+#[derive(Clone, Copy)]
+enum Alu {
+    Add,
+    Sub,
+    And,
+    Or,
+    Xor,
+}
+
+// reg, rm are x86 register indices 0..=7 (rax..rdi); imm32 instructions use
+// register-indirect addressing with an 8-bit displacement, which is enough
+// since xregs holds 32 entries at a 4-byte stride (max offset 124).
+fn modrm_disp8(reg: u8, rm: u8) -> u8 {
+    0b01_000_000 | (reg << 3) | rm
+}
+
+const RBX: u8 = 3;
+const RAX: u8 = 0;
+const RDX: u8 = 2;
+
+// mov r32, [rbx + disp8]
+fn emit_load_xreg(buf: &mut CodeBuffer, dst: u8, xreg: u8) {
+    buf.push_u8(0x8b);
+    buf.push_u8(modrm_disp8(dst, RBX));
+    buf.push_u8(xreg.wrapping_mul(4));
+}
+
+// mov [rbx + disp8], r32
+fn emit_store_xreg(buf: &mut CodeBuffer, xreg: u8, src: u8) {
+    buf.push_u8(0x89);
+    buf.push_u8(modrm_disp8(src, RBX));
+    buf.push_u8(xreg.wrapping_mul(4));
+}
+
+fn emit_reset_x0(buf: &mut CodeBuffer) {
+    // mov dword [rbx + 0], 0
+    buf.push_u8(0xc7);
+    buf.push_u8(modrm_disp8(0, RBX));
+    buf.push_u8(0);
+    buf.push_u32(0);
+}
+
+fn emit_alu_rr(buf: &mut CodeBuffer, op: Alu, instr: u32) {
+    let rd = rd(instr) as u8;
+    let rs1 = rs1(instr) as u8;
+    let rs2 = rs2(instr) as u8;
+    emit_load_xreg(buf, RAX, rs1);
+    emit_load_xreg(buf, RDX, rs2);
+    let opcode = match op {
+        Alu::Add => 0x01,
+        Alu::Sub => 0x29,
+        Alu::And => 0x21,
+        Alu::Or => 0x09,
+        Alu::Xor => 0x31,
+    };
+    // op eax, edx  (ADD/SUB/AND/OR/XOR r/m32, r32)
+    buf.push_u8(opcode);
+    buf.push_u8(0b11_000_000 | (RDX << 3) | RAX);
+    if rd != 0 {
+        emit_store_xreg(buf, rd, RAX);
+    }
+}
+
+fn emit_alu_ri(buf: &mut CodeBuffer, op: Alu, instr: u32) {
+    let rd = rd(instr) as u8;
+    let rs1 = rs1(instr) as u8;
+    let imm = imm_i(instr);
+    emit_load_xreg(buf, RAX, rs1);
+    let reg_field: u8 = match op {
+        Alu::Add => 0,
+        Alu::Or => 1,
+        Alu::And => 4,
+        Alu::Xor => 6,
+        Alu::Sub => unreachable!("no RV32I SUBI"),
+    };
+    // op eax, imm32 (group 1, opcode 0x81 /reg_field)
+    buf.push_u8(0x81);
+    buf.push_u8(0b11_000_000 | (reg_field << 3) | RAX);
+    buf.push_u32(imm as u32);
+    if rd != 0 {
+        emit_store_xreg(buf, rd, RAX);
+    }
+}
+
+// Falls back to the interpreter for an instruction we have not lowered:
+// `handler(cpu_ptr, instr)`, with `cpu_ptr`/`instr` baked in as immediates.
+fn emit_fallback_call(buf: &mut CodeBuffer, handler: fn(&mut CPU, u32), cpu_ptr: u64, instr: u32) {
+    // mov rdi, cpu_ptr
+    buf.push_u8(0x48);
+    buf.push_u8(0xbf);
+    buf.push_u64(cpu_ptr);
+    // mov esi, instr
+    buf.push_u8(0xbe);
+    buf.push_u32(instr);
+    emit_call(buf, handler as usize);
+}
+
+// Emits a call to `target`, using a near rel32 call when the displacement
+// fits (it usually won't across an mmap'd page and the main binary's .text,
+// but we still bounds-check rather than assume), else an absolute
+// mov-then-call sequence.
+fn emit_call(buf: &mut CodeBuffer, target: usize) {
+    let call_site_end = buf.addr() + 5; // E8 + rel32
+    let disp = target as i64 - call_site_end as i64;
+    if let Ok(rel32) = i32::try_from(disp) {
+        buf.push_u8(0xe8);
+        buf.push_u32(rel32 as u32);
+    } else {
+        // mov rax, target
+        buf.push_u8(0x48);
+        buf.push_u8(0xb8);
+        buf.push_u64(target as u64);
+        // call rax
+        buf.push_u8(0xff);
+        buf.push_u8(0xd0);
+    }
+}
+
+fn emit_prologue(buf: &mut CodeBuffer, regs_ptr: u64) {
+    // push rbx
+    buf.push_u8(0x53);
+    // mov rbx, regs_ptr
+    buf.push_u8(0x48);
+    buf.push_u8(0xbb);
+    buf.push_u64(regs_ptr);
+}
+
+fn emit_epilogue(buf: &mut CodeBuffer) {
+    // pop rbx
+    buf.push_u8(0x5b);
+    // ret
+    buf.push_u8(0xc3);
+}
+
+// Resolves the exec_* handler for instructions we don't lower to native
+// code ourselves (loads/stores/shifts/CSR/...), mirroring `cpu::decode`.
+fn fallback_handler(instr: u32) -> fn(&mut CPU, u32) {
+    let opcode = instr & 0x7f;
+    let funct3 = (instr >> 12) & 0x7;
+    let funct7 = (instr >> 25) & 0x7f;
+
+    match opcode {
+        LUI => exec_lui,
+        AUIPC => exec_auipc,
+        LOAD => match funct3 {
+            LB => exec_lb,
+            LH => exec_lh,
+            LW => exec_lw,
+            LBU => exec_lbu,
+            LHU => exec_lhu,
+            LWU => exec_lwu,
+            _ => cpu::exec_illegal,
+        },
+        S_TYPE => match funct3 {
+            SB => exec_sb,
+            SH => exec_sh,
+            SW => exec_sw,
+            _ => cpu::exec_illegal,
+        },
+        I_TYPE => match funct3 {
+            SLLI => exec_slli,
+            SLTI => cpu::exec_slti,
+            SLTIU => cpu::exec_sltiu,
+            SRI => match funct7 {
+                SRLI => exec_srli,
+                SRAI => exec_srai,
+                _ => cpu::exec_illegal,
+            },
+            _ => cpu::exec_illegal,
+        },
+        R_TYPE => match funct3 {
+            SLL => exec_sll,
+            SLT => exec_slt,
+            SLTU => exec_sltu,
+            SR => match funct7 {
+                SRL => exec_srl,
+                SRA => exec_sra,
+                _ => cpu::exec_illegal,
+            },
+            _ => cpu::exec_illegal,
+        },
+        FENCE => match funct3 {
+            FENCE_I => exec_fence_i,
+            _ => exec_fence,
+        },
+        CSR => match funct3 {
+            CSRRW => exec_csrrw,
+            CSRRS => exec_csrrs,
+            CSRRC => exec_csrrc,
+            CSRRWI => exec_csrrwi,
+            CSRRSI => exec_csrrsi,
+            CSRRCI => exec_csrrci,
+            _ => cpu::exec_illegal,
+        },
+        LOAD_FP => exec_flw,
+        STORE_FP => exec_fsw,
+        OP_FP => match funct7 {
+            FADD_S => exec_fadd_s,
+            FSUB_S => exec_fsub_s,
+            FMUL_S => exec_fmul_s,
+            FDIV_S => exec_fdiv_s,
+            FSQRT_S => exec_fsqrt_s,
+            FSGNJ_S => match funct3 {
+                FSGNJN => exec_fsgnjn_s,
+                FSGNJX => exec_fsgnjx_s,
+                _ => exec_fsgnj_s,
+            },
+            FCMP_S => match funct3 {
+                FLT => exec_flt_s,
+                FLE => exec_fle_s,
+                _ => exec_feq_s,
+            },
+            FCVT_S_W => exec_fcvt_s_w,
+            _ => exec_fcvt_w_s,
+        },
+        _ => cpu::exec_illegal,
+    }
+}
+
+// Whether `instr` hands control elsewhere (branch/jump/ecall/ebreak/mret)
+// and must therefore end the block; it is left for the interpreter.
+fn is_block_terminator(instr: u32) -> bool {
+    let opcode = instr & 0x7f;
+    let funct3 = (instr >> 12) & 0x7;
+    matches!(opcode, B_TYPE | JAL | JALR) || (opcode == CSR && funct3 == 0)
+}
+
+const MAX_BLOCK_LEN: u32 = 64;
+
+// Compiles the straight-line run of instructions starting at `start_pc`
+// (up to the first branch/jump/ecall/ebreak/mret) to native x86-64.
+pub fn compile_block(cpu: &mut CPU, start_pc: u32) -> CompiledBlock {
+    let cpu_ptr = cpu as *mut CPU as u64;
+    let regs_ptr = cpu.xregs.regs.as_mut_ptr() as u64;
+
+    let mut code = CodeBuffer::new(4096);
+    emit_prologue(&mut code, regs_ptr);
+
+    let mut pc = start_pc;
+    for _ in 0..MAX_BLOCK_LEN {
+        let instr = cpu.bus.load(pc, 32);
+        if is_block_terminator(instr) {
+            break;
+        }
+
+        emit_reset_x0(&mut code);
+
+        let opcode = instr & 0x7f;
+        let funct3 = (instr >> 12) & 0x7;
+        let funct7 = (instr >> 25) & 0x7f;
+        match opcode {
+            R_TYPE if funct3 == ADDSUB && funct7 == ADD => emit_alu_rr(&mut code, Alu::Add, instr),
+            R_TYPE if funct3 == ADDSUB && funct7 == SUB => emit_alu_rr(&mut code, Alu::Sub, instr),
+            R_TYPE if funct3 == AND => emit_alu_rr(&mut code, Alu::And, instr),
+            R_TYPE if funct3 == OR => emit_alu_rr(&mut code, Alu::Or, instr),
+            R_TYPE if funct3 == XOR => emit_alu_rr(&mut code, Alu::Xor, instr),
+            I_TYPE if funct3 == ADDI => emit_alu_ri(&mut code, Alu::Add, instr),
+            I_TYPE if funct3 == ANDI => emit_alu_ri(&mut code, Alu::And, instr),
+            I_TYPE if funct3 == ORI => emit_alu_ri(&mut code, Alu::Or, instr),
+            I_TYPE if funct3 == XORI => emit_alu_ri(&mut code, Alu::Xor, instr),
+            _ => emit_fallback_call(&mut code, fallback_handler(instr), cpu_ptr, instr),
+        }
+
+        pc = pc.wrapping_add(4);
+    }
+
+    emit_epilogue(&mut code);
+    code.make_executable();
+
+    let entry: unsafe extern "C" fn() = unsafe { std::mem::transmute(code.ptr) };
+    CompiledBlock {
+        code,
+        entry,
+        end: pc,
+    }
+}
@@ -0,0 +1,7 @@
+pub mod cpu;
+pub mod debug;
+pub mod elf;
+pub mod jit;
+pub mod memory;
+pub mod opcode;
+pub mod registers;
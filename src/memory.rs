@@ -0,0 +1,153 @@
+use std::ops::Range;
+
+pub const MEM_BASE: u32 = 0x8000_0000;
+pub const MEM_SIZE: u32 = 1024 * 1024 * 128;
+
+pub const UART_BASE: u32 = 0x1000_0000;
+pub const UART_SIZE: u32 = 0x100;
+// writing any byte to this offset prints it to stdout
+pub const UART_TX: u32 = 0x00;
+
+pub const FB_WIDTH: u32 = 320;
+pub const FB_HEIGHT: u32 = 240;
+pub const FB_BASE: u32 = 0x2000_0000;
+pub const FB_SIZE: u32 = FB_WIDTH * FB_HEIGHT * 4;
+
+// A memory-mapped peripheral that the bus can route loads/stores to.
+pub trait Device {
+    fn load(&self, offset: u32, size: u8) -> u32;
+    fn store(&mut self, offset: u32, size: u8, val: u32);
+}
+
+pub struct RAM {
+    data: Vec<u8>,
+}
+
+impl RAM {
+    fn new(size: u32) -> Self {
+        RAM {
+            data: vec![0; size as usize],
+        }
+    }
+}
+
+impl Device for RAM {
+    fn load(&self, offset: u32, size: u8) -> u32 {
+        load_bytes(&self.data, offset, size)
+    }
+
+    fn store(&mut self, offset: u32, size: u8, val: u32) {
+        store_bytes(&mut self.data, offset, size, val);
+    }
+}
+
+// Console/UART device: any store prints the low byte of `val` to stdout.
+pub struct UART;
+
+impl Device for UART {
+    fn load(&self, _offset: u32, _size: u8) -> u32 {
+        0
+    }
+
+    fn store(&mut self, _offset: u32, _size: u8, val: u32) {
+        print!("{}", (val & 0xff) as u8 as char);
+    }
+}
+
+// Simple RGBA8888 framebuffer device.
+pub struct Framebuffer {
+    pub pixels: Vec<u8>,
+}
+
+impl Framebuffer {
+    fn new(size: u32) -> Self {
+        Framebuffer {
+            pixels: vec![0; size as usize],
+        }
+    }
+}
+
+impl Device for Framebuffer {
+    fn load(&self, offset: u32, size: u8) -> u32 {
+        load_bytes(&self.pixels, offset, size)
+    }
+
+    fn store(&mut self, offset: u32, size: u8, val: u32) {
+        store_bytes(&mut self.pixels, offset, size, val);
+    }
+}
+
+// Bounds-checked so a load/store straddling the end of a region (or a
+// fallback access that lands outside RAM entirely) reads/writes as many
+// bytes as exist and treats the rest as zero, instead of panicking.
+fn load_bytes(data: &[u8], offset: u32, size: u8) -> u32 {
+    let offset = offset as usize;
+    let mut val: u32 = 0;
+    for i in 0..(size as usize / 8) {
+        let byte = data.get(offset + i).copied().unwrap_or(0);
+        val |= (byte as u32) << (i * 8);
+    }
+    val
+}
+
+fn store_bytes(data: &mut [u8], offset: u32, size: u8, val: u32) {
+    let offset = offset as usize;
+    for i in 0..(size as usize / 8) {
+        if let Some(byte) = data.get_mut(offset + i) {
+            *byte = ((val >> (i * 8)) & 0xff) as u8;
+        }
+    }
+}
+
+// RAM sits outside `devices` and is the fallback: any address not claimed
+// by a registered device (including one outside RAM's own nominal range)
+// is routed to it, the way an SoC's default memory decode works.
+pub struct BUS {
+    ram: RAM,
+    devices: Vec<(Range<u32>, Box<dyn Device>)>,
+}
+
+impl BUS {
+    pub fn new() -> Self {
+        let mut bus = BUS {
+            ram: RAM::new(MEM_SIZE),
+            devices: Vec::new(),
+        };
+        bus.devices.push((
+            UART_BASE..UART_BASE + UART_SIZE,
+            Box::new(UART) as Box<dyn Device>,
+        ));
+        bus.devices.push((
+            FB_BASE..FB_BASE + FB_SIZE,
+            Box::new(Framebuffer::new(FB_SIZE)) as Box<dyn Device>,
+        ));
+        bus
+    }
+
+    fn find_device(&self, addr: u32) -> Option<&(Range<u32>, Box<dyn Device>)> {
+        self.devices.iter().find(|(range, _)| range.contains(&addr))
+    }
+
+    fn find_device_mut(&mut self, addr: u32) -> Option<&mut (Range<u32>, Box<dyn Device>)> {
+        self.devices
+            .iter_mut()
+            .find(|(range, _)| range.contains(&addr))
+    }
+
+    pub fn load(&self, addr: u32, size: u8) -> u32 {
+        match self.find_device(addr) {
+            Some((range, device)) => device.load(addr - range.start, size),
+            None => self.ram.load(addr.wrapping_sub(MEM_BASE), size),
+        }
+    }
+
+    pub fn store(&mut self, addr: u32, size: u8, val: u32) {
+        match self.find_device_mut(addr) {
+            Some((range, device)) => {
+                let offset = addr - range.start;
+                device.store(offset, size, val);
+            }
+            None => self.ram.store(addr.wrapping_sub(MEM_BASE), size, val),
+        }
+    }
+}
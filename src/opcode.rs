@@ -0,0 +1,151 @@
+// RV32I opcode map and bit-field helpers.
+// see page 64 at https://riscv.org/wp-content/uploads/2016/06/riscv-spec-v2.1.pdf
+
+pub const LUI: u32 = 0b011_0111;
+pub const AUIPC: u32 = 0b001_0111;
+pub const JAL: u32 = 0b110_1111;
+pub const JALR: u32 = 0b110_0111;
+pub const B_TYPE: u32 = 0b110_0011;
+pub const LOAD: u32 = 0b000_0011;
+pub const S_TYPE: u32 = 0b010_0011;
+pub const I_TYPE: u32 = 0b001_0011;
+pub const R_TYPE: u32 = 0b011_0011;
+pub const FENCE: u32 = 0b000_1111;
+pub const CSR: u32 = 0b111_0011;
+
+// RV32F opcodes
+// see page 78 at https://riscv.org/wp-content/uploads/2016/06/riscv-spec-v2.1.pdf
+pub const LOAD_FP: u32 = 0b000_0111;
+pub const STORE_FP: u32 = 0b010_0111;
+pub const OP_FP: u32 = 0b101_0011;
+
+// FENCE funct3 (0x0 is the ordinary FENCE, handled by the default arm)
+pub const FENCE_I: u32 = 0x1;
+
+// B_TYPE funct3
+pub const BEQ: u32 = 0x0;
+pub const BNE: u32 = 0x1;
+pub const BLT: u32 = 0x4;
+pub const BGE: u32 = 0x5;
+pub const BLTU: u32 = 0x6;
+pub const BGEU: u32 = 0x7;
+
+// LOAD funct3
+pub const LB: u32 = 0x0;
+pub const LH: u32 = 0x1;
+pub const LW: u32 = 0x2;
+pub const LBU: u32 = 0x4;
+pub const LHU: u32 = 0x5;
+pub const LWU: u32 = 0x6;
+
+// S_TYPE funct3
+pub const SB: u32 = 0x0;
+pub const SH: u32 = 0x1;
+pub const SW: u32 = 0x2;
+
+// I_TYPE funct3
+pub const ADDI: u32 = 0x0;
+pub const SLLI: u32 = 0x1;
+pub const SLTI: u32 = 0x2;
+pub const SLTIU: u32 = 0x3;
+pub const XORI: u32 = 0x4;
+pub const SRI: u32 = 0x5;
+pub const ORI: u32 = 0x6;
+pub const ANDI: u32 = 0x7;
+
+// I_TYPE/R_TYPE shift funct7
+pub const SRLI: u32 = 0x00;
+pub const SRAI: u32 = 0x20;
+
+// R_TYPE funct3
+pub const ADDSUB: u32 = 0x0;
+pub const SLL: u32 = 0x1;
+pub const SLT: u32 = 0x2;
+pub const SLTU: u32 = 0x3;
+pub const XOR: u32 = 0x4;
+pub const SR: u32 = 0x5;
+pub const OR: u32 = 0x6;
+pub const AND: u32 = 0x7;
+
+// R_TYPE funct7
+pub const ADD: u32 = 0x00;
+pub const SUB: u32 = 0x20;
+pub const SRL: u32 = 0x00;
+pub const SRA: u32 = 0x20;
+
+// CSR funct3
+pub const ECALL: u32 = 0x0;
+pub const EBREAK: u32 = 0x0;
+pub const CSRRW: u32 = 0x1;
+pub const CSRRS: u32 = 0x2;
+pub const CSRRC: u32 = 0x3;
+pub const CSRRWI: u32 = 0x5;
+pub const CSRRSI: u32 = 0x6;
+pub const CSRRCI: u32 = 0x7;
+
+// LOAD_FP/STORE_FP funct3
+pub const FLW: u32 = 0x2;
+pub const FSW: u32 = 0x2;
+
+// OP_FP funct7 (funct5 in bits 31:27, with bits 26:25 as fmt = 00 for .S)
+pub const FADD_S: u32 = 0x00;
+pub const FSUB_S: u32 = 0x04;
+pub const FMUL_S: u32 = 0x08;
+pub const FDIV_S: u32 = 0x0c;
+pub const FSQRT_S: u32 = 0x2c;
+pub const FSGNJ_S: u32 = 0x10;
+pub const FCMP_S: u32 = 0x50;
+pub const FCVT_W_S: u32 = 0x60;
+pub const FCVT_S_W: u32 = 0x68;
+
+// FSGNJ_S funct3
+pub const FSGNJ: u32 = 0x0;
+pub const FSGNJN: u32 = 0x1;
+pub const FSGNJX: u32 = 0x2;
+
+// FCMP_S funct3
+pub const FLE: u32 = 0x0;
+pub const FLT: u32 = 0x1;
+pub const FEQ: u32 = 0x2;
+
+pub fn rd(instr: u32) -> u32 {
+    (instr >> 7) & 0x1f
+}
+
+pub fn rs1(instr: u32) -> u32 {
+    (instr >> 15) & 0x1f
+}
+
+pub fn rs2(instr: u32) -> u32 {
+    (instr >> 20) & 0x1f
+}
+
+pub fn shamt(instr: u32) -> u32 {
+    rs2(instr)
+}
+
+pub fn imm_i(instr: u32) -> i32 {
+    (instr as i32) >> 20
+}
+
+pub fn imm_s(instr: u32) -> i32 {
+    (((instr & 0xfe00_0000) as i32) >> 20) | ((instr >> 7) & 0x1f) as i32
+}
+
+pub fn imm_b(instr: u32) -> i32 {
+    (((instr & 0x8000_0000) as i32) >> 19)
+        | ((instr & 0x80) << 4) as i32
+        | ((instr >> 20) & 0x7e0) as i32
+        | ((instr >> 7) & 0x1e) as i32
+}
+
+pub fn imm_u(instr: u32) -> u32 {
+    instr & 0xffff_f000
+}
+
+pub fn imm_j(instr: u32) -> i32 {
+    (((instr & 0x8000_0000) as i32) >> 11)
+        | (instr & 0xff000) as i32
+        | ((instr >> 9) & 0x800) as i32
+        | ((instr >> 20) & 0x7fe) as i32
+}
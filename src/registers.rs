@@ -0,0 +1,10 @@
+#[derive(Debug, Clone)]
+pub struct XREGS {
+    pub regs: [u32; 32],
+}
+
+impl XREGS {
+    pub fn new() -> Self {
+        XREGS { regs: [0; 32] }
+    }
+}
@@ -0,0 +1,42 @@
+mod helper;
+
+use emurv::cpu::{self, CPU};
+use emurv::opcode::{ADDI, ANDI, LUI, SB};
+
+#[test]
+fn substore_into_instruction_word_invalidates_its_decode() {
+    let mut cpu = CPU::new();
+    let pc = cpu.pc;
+
+    helper::set_register_val(&mut cpu, 1, 5); // x1 = 5
+
+    // addi x5, x1, 3 -> 8; cached as the ADDI handler at `pc`.
+    let addi = helper::set_i_type_instruction(3, 1, ADDI as u8, 5);
+    cpu.bus.store(pc, 32, addi);
+    let fetched = cpu.fetch();
+    cpu.execute(fetched);
+    assert_eq!(cpu.xregs.regs[5], 8);
+
+    // Same bit pattern except funct3, which lives entirely in byte 1:
+    // andi x5, x1, 3 -> 1.
+    let andi = helper::set_i_type_instruction(3, 1, ANDI as u8, 5);
+    let changed_byte = (andi >> 8) & 0xff;
+
+    // x6 = pc (via LUI, since pc's lower 12 bits are 0), x7 = changed byte
+    let lui = helper::set_u_type_instruction(pc as i32, 6, LUI as u8);
+    cpu::exec_lui(&mut cpu, lui);
+    helper::set_register_val(&mut cpu, 7, changed_byte as i16);
+
+    // sb x7, 1(x6): a 1-byte store into byte 1 of the cached instruction
+    // word, through the production exec_sb path (not a raw bus.store).
+    let sb = helper::set_s_type_instruction(1, 7, 6, SB as u8);
+    cpu::exec_sb(&mut cpu, sb);
+
+    let fetched = cpu.fetch();
+    assert_eq!(fetched, andi, "the byte store must have landed in memory");
+    cpu.execute(fetched);
+    assert_eq!(
+        cpu.xregs.regs[5], 1,
+        "a sub-word store must invalidate the stale decode cached at this pc"
+    );
+}
@@ -0,0 +1,41 @@
+mod helper;
+
+use emurv::cpu;
+use emurv::opcode::CSR;
+
+// Machine-mode CSR addresses mirrored from `cpu.rs` (not exported, since
+// only `cpu::CPU` needs them internally).
+const CSR_MTVEC: usize = 0x305;
+const CSR_MCAUSE: usize = 0x342;
+
+// opcode-only CSR instruction: funct3 = 0 and imm_i = 0 decode to ECALL.
+fn ecall_instr() -> u32 {
+    CSR
+}
+
+#[test]
+fn recognized_syscall_returns_without_trapping() {
+    let mut cpu = cpu::CPU::new();
+    helper::set_register_val(&mut cpu, 17, 64); // a7 = SYS_WRITE
+    helper::set_register_val(&mut cpu, 10, 1); // a0 = fd 1
+    helper::set_register_val(&mut cpu, 11, 0); // a1 = ptr
+    helper::set_register_val(&mut cpu, 12, 0); // a2 = len 0
+
+    let pc_before = cpu.pc;
+    cpu::exec_ecall(&mut cpu, ecall_instr());
+
+    assert_eq!(cpu.pc, pc_before, "a host syscall must not redirect pc to mtvec");
+    assert_eq!(cpu.csrs[CSR_MCAUSE], 0, "a host syscall must not raise a trap");
+    assert_eq!(cpu.xregs.regs[10], 0); // write() of 0 bytes returns 0
+}
+
+#[test]
+fn unrecognized_syscall_traps_to_mtvec() {
+    let mut cpu = cpu::CPU::new();
+    helper::set_register_val(&mut cpu, 17, 0x7ff); // not a syscall we emulate
+
+    cpu::exec_ecall(&mut cpu, ecall_instr());
+
+    assert_eq!(cpu.csrs[CSR_MCAUSE], 11); // CAUSE_ECALL_FROM_M
+    assert_eq!(cpu.pc, cpu.csrs[CSR_MTVEC].wrapping_sub(4));
+}
@@ -0,0 +1,83 @@
+use emurv::cpu::CPU;
+use emurv::elf::ElfError;
+use emurv::memory::MEM_BASE;
+
+const EM_RISCV: u16 = 243;
+
+// Builds a minimal ELF32/RISC-V header (52 bytes) followed by `phnum`
+// identical program header slots (32 bytes each, zeroed) that callers then
+// fill in with `set_ph`.
+fn build_header(e_entry: u32, phnum: u16) -> Vec<u8> {
+    let mut bytes = vec![0u8; 52 + (phnum as usize) * 32];
+    bytes[0..4].copy_from_slice(b"\x7fELF");
+    bytes[4] = 1; // EI_CLASS = ELFCLASS32
+    bytes[5] = 1; // EI_DATA = ELFDATA2LSB
+    bytes[18..20].copy_from_slice(&EM_RISCV.to_le_bytes());
+    bytes[24..28].copy_from_slice(&e_entry.to_le_bytes());
+    bytes[28..32].copy_from_slice(&52u32.to_le_bytes()); // e_phoff
+    bytes[42..44].copy_from_slice(&32u16.to_le_bytes()); // e_phentsize
+    bytes[44..46].copy_from_slice(&phnum.to_le_bytes()); // e_phnum
+    bytes
+}
+
+fn set_ph(bytes: &mut [u8], index: usize, p_type: u32, p_offset: u32, p_vaddr: u32, p_filesz: u32, p_memsz: u32) {
+    let off = 52 + index * 32;
+    bytes[off..off + 4].copy_from_slice(&p_type.to_le_bytes());
+    bytes[off + 4..off + 8].copy_from_slice(&p_offset.to_le_bytes());
+    bytes[off + 8..off + 12].copy_from_slice(&p_vaddr.to_le_bytes());
+    bytes[off + 16..off + 20].copy_from_slice(&p_filesz.to_le_bytes());
+    bytes[off + 20..off + 24].copy_from_slice(&p_memsz.to_le_bytes());
+}
+
+const PT_LOAD: u32 = 1;
+
+#[test]
+fn loads_a_valid_segment_and_sets_pc() {
+    let mut bytes = build_header(MEM_BASE, 1);
+    let data_offset = bytes.len() as u32; // right after the header + ph table
+    let nop = 0x13u32.to_le_bytes(); // addi x0, x0, 0
+    bytes.extend_from_slice(&nop);
+    set_ph(&mut bytes, 0, PT_LOAD, data_offset, MEM_BASE, 4, 4);
+
+    let mut cpu = CPU::new();
+    cpu.load_elf(&bytes).expect("valid ELF should load");
+
+    assert_eq!(cpu.pc, MEM_BASE);
+    assert_eq!(cpu.bus.load(MEM_BASE, 32), 0x13);
+}
+
+#[test]
+fn zero_fills_bss_past_filesz() {
+    let mut bytes = build_header(MEM_BASE, 1);
+    let data_offset = bytes.len() as u32;
+    bytes.extend_from_slice(&[0xaa]); // one byte of file content
+    set_ph(&mut bytes, 0, PT_LOAD, data_offset, MEM_BASE, 1, 4); // memsz > filesz
+
+    let mut cpu = CPU::new();
+    cpu.load_elf(&bytes).expect("valid ELF should load");
+
+    assert_eq!(cpu.bus.load(MEM_BASE, 8), 0xaa);
+    assert_eq!(cpu.bus.load(MEM_BASE + 1, 8), 0); // .bss, zero-filled
+}
+
+#[test]
+fn truncated_program_header_table_is_rejected_not_panicked() {
+    let mut bytes = build_header(MEM_BASE, 1);
+    bytes.truncate(52 + 10); // still advertises e_phnum = 1 but lacks the bytes for it
+
+    let mut cpu = CPU::new();
+    let result = cpu.load_elf(&bytes);
+    assert!(matches!(result, Err(ElfError::BadProgramHeaders)));
+}
+
+#[test]
+fn segment_file_range_past_eof_is_rejected_not_panicked() {
+    let mut bytes = build_header(MEM_BASE, 1);
+    let data_offset = bytes.len() as u32;
+    // No segment data appended, but p_offset/p_filesz claim some anyway.
+    set_ph(&mut bytes, 0, PT_LOAD, data_offset, MEM_BASE, 16, 16);
+
+    let mut cpu = CPU::new();
+    let result = cpu.load_elf(&bytes);
+    assert!(matches!(result, Err(ElfError::SegmentOutOfBounds)));
+}
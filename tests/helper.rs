@@ -1,6 +1,6 @@
 use emurv::{
     cpu,
-    opcode::{I_TYPE, R_TYPE},
+    opcode::{I_TYPE, R_TYPE, S_TYPE},
 };
 
 pub fn set_i_type_instruction(imm: i16, rs1: u8, funct3: u8, rd: u8) -> u32 {
@@ -26,6 +26,17 @@ pub fn set_j_type_instruction(imm: i32, rd: u8, opcode: u8) -> u32 {
     return (instr_imm) as u32 | ((rd as u32 & 0x1f) << 7) | ((opcode as u32) & 0x7f);
 }
 
+pub fn set_s_type_instruction(imm: i16, rs2: u8, rs1: u8, funct3: u8) -> u32 {
+    // |31-25|24-20|19-15|14-12|11-7|6-0|
+    let imm = imm as u32;
+    return (((imm >> 5) & 0x7f) << 25)
+        | ((rs2 as u32 & 0x1f) << 20)
+        | ((rs1 as u32 & 0x1f) << 15)
+        | ((funct3 as u32 & 0x7) << 12)
+        | ((imm & 0x1f) << 7)
+        | ((S_TYPE as u32) & 0x7f);
+}
+
 pub fn set_r_type_instruction(funct7: u8, rs2: u8, rs1: u8, rd: u8) -> u32 {
     // |31-20|19-15|14-12|11-7|6-0|
     return ((funct7 as u32 & 0x7f) << 25)
@@ -0,0 +1,126 @@
+mod helper;
+
+use emurv::cpu::CPU;
+use emurv::memory::MEM_BASE;
+use emurv::opcode::{ADDI, CSR, LUI, SB};
+
+// addi x0, x0, 0
+const NOP: u32 = 0x13;
+
+fn store(cpu: &mut CPU, addr: u32, instr: u32) {
+    cpu.bus.store(addr, 32, instr);
+}
+
+// A straight-line block of arithmetic (all ADDI/ADD, so every instruction is
+// natively lowered) ending in an ecall, identical whether interpreted or
+// JIT-compiled: x1 = 5, x2 = 10, x3 = x1 + x2, exit(x3).
+fn write_arith_program(cpu: &mut CPU) {
+    let base = cpu.pc;
+    store(cpu, base, helper::set_i_type_instruction(5, 0, ADDI as u8, 1)); // addi x1,x0,5
+    store(
+        cpu,
+        base + 4,
+        helper::set_i_type_instruction(10, 0, ADDI as u8, 2),
+    ); // addi x2,x0,10
+    store(
+        cpu,
+        base + 8,
+        helper::set_r_type_instruction(0x00, 2, 1, 3),
+    ); // add x3,x1,x2
+    store(
+        cpu,
+        base + 12,
+        helper::set_i_type_instruction(93, 0, ADDI as u8, 17),
+    ); // addi x17,x0,93 (a7 = SYS_EXIT)
+    store(
+        cpu,
+        base + 16,
+        helper::set_i_type_instruction(0, 3, ADDI as u8, 10),
+    ); // addi x10,x3,0 (a0 = x3)
+    store(cpu, base + 20, CSR); // ecall
+}
+
+#[test]
+fn jit_and_interpreter_agree_on_a_straight_line_block() {
+    let mut interpreted = CPU::new();
+    write_arith_program(&mut interpreted);
+    let exit_interpreted = interpreted.run();
+
+    let mut jitted = CPU::new();
+    jitted.jit_enabled = true;
+    write_arith_program(&mut jitted);
+    let exit_jitted = jitted.run();
+
+    assert_eq!(exit_interpreted, 15);
+    assert_eq!(exit_jitted, 15);
+    assert_eq!(interpreted.xregs.regs[1..4], jitted.xregs.regs[1..4]);
+}
+
+// A block that stores into its own later instructions (still within the
+// same compiled block's [start, end) range) must not free the
+// `CompiledBlock` while its native code is still executing.
+#[test]
+fn self_modifying_store_inside_a_running_jit_block_does_not_crash() {
+    let mut cpu = CPU::new();
+    cpu.jit_enabled = true;
+    let base = cpu.pc;
+    let target = base + 40;
+
+    let new_instr = helper::set_i_type_instruction(99, 0, ADDI as u8, 5); // addi x5,x0,99
+    let bytes = new_instr.to_le_bytes();
+
+    store(&mut cpu, base, helper::set_u_type_instruction(MEM_BASE as i32, 6, LUI as u8)); // lui x6, MEM_BASE
+    store(&mut cpu, base + 4, helper::set_i_type_instruction(40, 6, ADDI as u8, 6)); // addi x6,x6,40 -> &target
+
+    let mut pc = base + 8;
+    for (i, byte) in bytes.iter().enumerate() {
+        store(
+            &mut cpu,
+            pc,
+            helper::set_i_type_instruction(*byte as i16, 0, ADDI as u8, 1),
+        ); // addi x1,x0,byte
+        pc += 4;
+        store(
+            &mut cpu,
+            pc,
+            helper::set_s_type_instruction(i as i16, 1, 6, SB as u8),
+        ); // sb x1, i(x6)
+        pc += 4;
+    }
+    assert_eq!(pc, target);
+
+    store(&mut cpu, target, NOP); // overwritten at runtime, still in-block
+    store(
+        &mut cpu,
+        target + 4,
+        helper::set_i_type_instruction(93, 0, ADDI as u8, 17),
+    ); // addi x17,x0,93
+    store(
+        &mut cpu,
+        target + 8,
+        helper::set_i_type_instruction(7, 0, ADDI as u8, 10),
+    ); // addi x10,x0,7
+    store(&mut cpu, target + 12, CSR); // ecall, ends the block
+
+    let exit_code = cpu.run();
+
+    assert_eq!(exit_code, 7, "block must run to completion without crashing");
+}
+
+// CompiledBlocks bake in absolute pointers to the CPU they were compiled
+// against; relocating the CPU afterward would leave them dangling. `step`
+// must catch this rather than silently running stale blocks.
+#[test]
+#[should_panic(expected = "CPU moved while jit_enabled")]
+fn moving_the_cpu_after_jit_compiles_a_block_panics() {
+    let mut cpu = CPU::new();
+    cpu.jit_enabled = true;
+    write_arith_program(&mut cpu);
+    cpu.run(); // compiles a block, bakes its address into the native code, and halts
+
+    // Moving the CPU invalidates every pointer baked into jit_cache; the
+    // very next `step` must catch this before touching any of them.
+    let mut moved = cpu;
+    moved.halted = false;
+    moved.run();
+}
@@ -0,0 +1,41 @@
+use emurv::memory::{BUS, FB_BASE, MEM_BASE, MEM_SIZE, UART_BASE};
+
+#[test]
+fn ram_round_trips_a_word() {
+    let mut bus = BUS::new();
+    bus.store(MEM_BASE + 4, 32, 0xdead_beef);
+    assert_eq!(bus.load(MEM_BASE + 4, 32), 0xdead_beef);
+}
+
+#[test]
+fn framebuffer_round_trips_a_pixel() {
+    let mut bus = BUS::new();
+    bus.store(FB_BASE, 32, 0x11223344);
+    assert_eq!(bus.load(FB_BASE, 32), 0x11223344);
+}
+
+#[test]
+fn uart_load_does_not_touch_ram() {
+    let mut bus = BUS::new();
+    bus.store(UART_BASE, 8, b'x' as u32); // just prints, has no load-back state
+    assert_eq!(bus.load(UART_BASE, 8), 0);
+}
+
+#[test]
+fn address_below_ram_falls_back_without_panicking() {
+    let mut bus = BUS::new();
+    // Not claimed by UART, FB, or RAM's nominal start — exercises the
+    // fallback-to-RAM path's own bounds check rather than a real access.
+    bus.store(0, 32, 0xffff_ffff);
+    assert_eq!(bus.load(0, 32), 0);
+}
+
+#[test]
+fn straddling_a_region_end_does_not_panic() {
+    let mut bus = BUS::new();
+    let last_word = MEM_BASE + MEM_SIZE - 2;
+    // A 4-byte access starting 2 bytes before the end of RAM's backing
+    // store must not index out of bounds.
+    bus.store(last_word, 32, 0xffff_ffff);
+    let _ = bus.load(last_word, 32);
+}
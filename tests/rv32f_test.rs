@@ -0,0 +1,81 @@
+mod helper;
+
+use emurv::cpu::{self, CPU};
+use emurv::opcode::{ADDI, ANDI, FADD_S, FLW, FSW, LOAD_FP, STORE_FP};
+
+fn set_fregs(cpu: &mut CPU, reg: u8, val: f32) {
+    cpu.fregs[reg as usize] = val;
+}
+
+#[test]
+fn fadd_s_adds_two_fregs() {
+    let mut cpu = CPU::new();
+    set_fregs(&mut cpu, 1, 1.5);
+    set_fregs(&mut cpu, 2, 2.25);
+
+    let instr = helper::set_r_type_instruction(FADD_S as u8, 2, 1, 3);
+    cpu::exec_fadd_s(&mut cpu, instr);
+
+    assert_eq!(cpu.fregs[3], 3.75);
+}
+
+#[test]
+fn flw_and_fsw_round_trip_through_the_bus() {
+    let mut cpu = CPU::new();
+    let addr = cpu.pc;
+    set_fregs(&mut cpu, 5, 42.5);
+
+    // fsw f5, 0(x6): store f5 to `addr`, held in x6 since x0 is always 0.
+    cpu.xregs.regs[6] = addr;
+    let fsw = (helper::set_s_type_instruction(0, 5, 6, FSW as u8) & !0x7f) | STORE_FP;
+    cpu::exec_fsw(&mut cpu, fsw);
+
+    let flw = (helper::set_i_type_instruction(0, 6, FLW as u8, 7) & !0x7f) | LOAD_FP;
+    cpu::exec_flw(&mut cpu, flw);
+
+    assert_eq!(cpu.fregs[7], 42.5);
+}
+
+#[test]
+fn fsw_invalidates_the_decode_cache_at_the_stored_address() {
+    let mut cpu = CPU::new();
+    let pc = cpu.pc;
+
+    // addi x5,x0,1 decoded and cached at `pc`.
+    let addi = helper::set_i_type_instruction(1, 0, ADDI as u8, 5);
+    cpu.bus.store(pc, 32, addi);
+    let fetched = cpu.fetch();
+    cpu.execute(fetched);
+    assert_eq!(cpu.xregs.regs[5], 1);
+
+    // Overwrite the same word with `andi x5,x0,1` via fsw instead of a
+    // plain store, and rewind pc to re-fetch it.
+    let andi = helper::set_i_type_instruction(1, 0, ANDI as u8, 5);
+    set_fregs(&mut cpu, 1, f32::from_bits(andi));
+    cpu.xregs.regs[2] = pc;
+    let fsw = (helper::set_s_type_instruction(0, 1, 2, FSW as u8) & !0x7f) | STORE_FP;
+    cpu::exec_fsw(&mut cpu, fsw);
+
+    cpu.pc = pc;
+    let fetched = cpu.fetch();
+    assert_eq!(fetched, andi, "the fsw store must have landed in memory");
+    cpu.execute(fetched);
+
+    // A stale cached `exec_addi` would leave x5 == 1 (x0 + 1); the
+    // freshly decoded `andi` must produce x0 & 1 == 0.
+    assert_eq!(cpu.xregs.regs[5], 0);
+}
+
+#[test]
+fn fcvt_round_trips_an_integer() {
+    let mut cpu = CPU::new();
+    helper::set_register_val(&mut cpu, 1, 7);
+
+    let to_float = helper::set_r_type_instruction(0, 0, 1, 2);
+    cpu::exec_fcvt_s_w(&mut cpu, to_float);
+    assert_eq!(cpu.fregs[2], 7.0);
+
+    let to_int = helper::set_r_type_instruction(0, 0, 2, 3);
+    cpu::exec_fcvt_w_s(&mut cpu, to_int);
+    assert_eq!(cpu.xregs.regs[3], 7);
+}
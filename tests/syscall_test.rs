@@ -0,0 +1,29 @@
+mod helper;
+
+use emurv::cpu;
+
+#[test]
+fn sys_exit_halts_and_records_the_exit_code() {
+    let mut cpu = cpu::CPU::new();
+    helper::set_register_val(&mut cpu, 17, 93); // a7 = SYS_EXIT
+    helper::set_register_val(&mut cpu, 10, 7); // a0 = exit code
+
+    cpu::exec_ecall(&mut cpu, 0);
+
+    assert!(cpu.halted);
+    assert_eq!(cpu.exit_code, 7);
+}
+
+#[test]
+fn sys_read_from_an_unsupported_fd_returns_zero_bytes() {
+    let mut cpu = cpu::CPU::new();
+    let ptr = cpu.pc;
+    helper::set_register_val(&mut cpu, 17, 63); // a7 = SYS_READ
+    helper::set_register_val(&mut cpu, 10, 1); // a0 = fd 1 (not stdin)
+    cpu.xregs.regs[11] = ptr; // a1 = buf ptr
+    helper::set_register_val(&mut cpu, 12, 8); // a2 = len
+
+    cpu::exec_ecall(&mut cpu, 0);
+
+    assert_eq!(cpu.xregs.regs[10], 0, "read() from a fd we don't emulate returns 0");
+}